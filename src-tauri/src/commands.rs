@@ -1,9 +1,10 @@
-use tauri::AppHandle;
-use crate::db;
+use tauri::{AppHandle, State};
+use crate::db::DbPool;
 use serde::{Deserialize, Serialize};
 use rusqlite::OptionalExtension;
 use uuid::Uuid;
 use chrono::Timelike;
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Member {
@@ -25,10 +26,29 @@ pub struct AttendanceRecord {
     role: Option<String>,
 }
 
+/// Looks up a single setting, preferring a role-scoped override
+/// (`"<base_key>:<role>"`) over the global `base_key`.
+fn resolve_setting(conn: &rusqlite::Connection, base_key: &str, role: &str) -> Option<String> {
+    let scoped_key = format!("{}:{}", base_key, role);
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", [&scoped_key], |row| row.get(0))
+        .optional().ok().flatten()
+        .or_else(|| {
+            conn.query_row("SELECT value FROM settings WHERE key = ?1", [base_key], |row| row.get(0))
+                .optional().ok().flatten()
+        })
+}
+
+/// Parses an `HH:MM` setting value into minutes since midnight.
+fn parse_hhmm_to_minutes(value: &str) -> Option<u32> {
+    let (hour, minute) = value.split_once(':')?;
+    Some(hour.parse::<u32>().ok()? * 60 + minute.parse::<u32>().ok()?)
+}
+
 #[tauri::command]
-pub fn mark_attendance(app_handle: AppHandle, prefect_number: String, role: String) -> Result<AttendanceRecord, String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
-    
+pub fn mark_attendance(pool: State<'_, DbPool>, prefect_number: String, role: String) -> Result<AttendanceRecord, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    let now = chrono::Local::now();
+
     // 1. Find or create member
     let member_id: String = conn.query_row(
         "SELECT id FROM members WHERE prefect_number = ?1",
@@ -45,7 +65,7 @@ pub fn mark_attendance(app_handle: AppHandle, prefect_number: String, role: Stri
     });
 
     // 2. Check if already marked for today
-    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let date = now.format("%Y-%m-%d").to_string();
     let exists: bool = conn.query_row(
         "SELECT EXISTS(SELECT 1 FROM attendance WHERE member_id = ?1 AND date = ?2)",
         [&member_id, &date],
@@ -56,17 +76,27 @@ pub fn mark_attendance(app_handle: AppHandle, prefect_number: String, role: Stri
         return Err(format!("Attendance already marked for {} today", prefect_number));
     }
 
-    // 3. Insert attendance
-    let id = Uuid::new_v4().to_string();
-    let timestamp = chrono::Local::now().to_rfc3339();
-    // Simple status logic: Late if after 7:00 AM
-    let now = chrono::Local::now();
-    let status = if now.hour() > 7 || (now.hour() == 7 && now.minute() > 0) {
-        "Late"
-    } else {
-        "Present"
+    // 3. Work out status from the settings-configured thresholds, falling
+    // back to the historical "Late after 7:00 AM" default when unset.
+    let minutes_since_midnight = now.hour() * 60 + now.minute();
+
+    let late_threshold_minutes = resolve_setting(&conn, "late_threshold", &role)
+        .and_then(|v| parse_hhmm_to_minutes(&v))
+        .unwrap_or(7 * 60);
+
+    let absent_threshold_minutes = resolve_setting(&conn, "absent_threshold", &role)
+        .and_then(|v| parse_hhmm_to_minutes(&v));
+
+    let status = match absent_threshold_minutes {
+        Some(absent_minutes) if minutes_since_midnight > absent_minutes => "Absent",
+        _ if minutes_since_midnight > late_threshold_minutes => "Late",
+        _ => "Present",
     };
 
+    // 4. Insert attendance
+    let id = Uuid::new_v4().to_string();
+    let timestamp = now.to_rfc3339();
+
     conn.execute(
         "INSERT INTO attendance (id, member_id, date, timestamp, status) VALUES (?1, ?2, ?3, ?4, ?5)",
         [&id, &member_id, &date, &timestamp, status],
@@ -84,13 +114,13 @@ pub fn mark_attendance(app_handle: AppHandle, prefect_number: String, role: Stri
 }
 
 #[tauri::command]
-pub fn get_attendance_by_date(app_handle: AppHandle, date: String) -> Result<Vec<AttendanceRecord>, String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
-    
+pub fn get_attendance_by_date(pool: State<'_, DbPool>, date: String) -> Result<Vec<AttendanceRecord>, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+
     let mut stmt = conn.prepare(
-        "SELECT a.id, a.member_id, a.date, a.timestamp, a.status, m.prefect_number, m.role 
-         FROM attendance a 
-         JOIN members m ON a.member_id = m.id 
+        "SELECT a.id, a.member_id, a.date, a.timestamp, a.status, m.prefect_number, m.role
+         FROM attendance a
+         JOIN members m ON a.member_id = m.id
          WHERE a.date = ?1"
     ).map_err(|e| e.to_string())?;
 
@@ -110,17 +140,17 @@ pub fn get_attendance_by_date(app_handle: AppHandle, date: String) -> Result<Vec
     for record in records {
         result.push(record.map_err(|e| e.to_string())?);
     }
-    
+
     Ok(result)
 }
 
 #[tauri::command]
-pub fn get_all_attendance(app_handle: AppHandle) -> Result<Vec<AttendanceRecord>, String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
-    
+pub fn get_all_attendance(pool: State<'_, DbPool>) -> Result<Vec<AttendanceRecord>, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+
     let mut stmt = conn.prepare(
-        "SELECT a.id, a.member_id, a.date, a.timestamp, a.status, m.prefect_number, m.role 
-         FROM attendance a 
+        "SELECT a.id, a.member_id, a.date, a.timestamp, a.status, m.prefect_number, m.role
+         FROM attendance a
          JOIN members m ON a.member_id = m.id"
     ).map_err(|e| e.to_string())?;
 
@@ -140,14 +170,83 @@ pub fn get_all_attendance(app_handle: AppHandle) -> Result<Vec<AttendanceRecord>
     for record in records {
         result.push(record.map_err(|e| e.to_string())?);
     }
-    
+
     Ok(result)
 }
 
 #[tauri::command]
-pub fn get_member_list(app_handle: AppHandle) -> Result<Vec<Member>, String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
-    
+pub fn query_attendance(
+    pool: State<'_, DbPool>,
+    from: Option<String>,
+    to: Option<String>,
+    status: Option<String>,
+    role: Option<String>,
+    prefect_number: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<AttendanceRecord>, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+
+    let mut query = String::from(
+        "SELECT a.id, a.member_id, a.date, a.timestamp, a.status, m.prefect_number, m.role
+         FROM attendance a
+         JOIN members m ON a.member_id = m.id
+         WHERE 1 = 1"
+    );
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(from) = from {
+        query.push_str(" AND a.date >= ?");
+        params.push(Box::new(from));
+    }
+    if let Some(to) = to {
+        query.push_str(" AND a.date <= ?");
+        params.push(Box::new(to));
+    }
+    if let Some(status) = status {
+        query.push_str(" AND a.status = ?");
+        params.push(Box::new(status));
+    }
+    if let Some(role) = role {
+        query.push_str(" AND m.role = ?");
+        params.push(Box::new(role));
+    }
+    if let Some(prefect_number) = prefect_number {
+        query.push_str(" AND m.prefect_number LIKE ?");
+        params.push(Box::new(format!("%{}%", prefect_number)));
+    }
+
+    query.push_str(" ORDER BY a.date DESC, a.timestamp DESC LIMIT ? OFFSET ?");
+    params.push(Box::new(limit.unwrap_or(100)));
+    params.push(Box::new(offset.unwrap_or(0)));
+
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+    let records = stmt.query_map(param_refs.as_slice(), |row| {
+        Ok(AttendanceRecord {
+            id: row.get(0)?,
+            member_id: row.get(1)?,
+            date: row.get(2)?,
+            timestamp: row.get(3)?,
+            status: row.get(4)?,
+            prefect_number: row.get(5)?,
+            role: row.get(6)?,
+        })
+    }).map_err(|e| e.to_string())?;
+
+    let mut result = Vec::new();
+    for record in records {
+        result.push(record.map_err(|e| e.to_string())?);
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_member_list(pool: State<'_, DbPool>) -> Result<Vec<Member>, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+
     let mut stmt = conn.prepare("SELECT id, name, role, prefect_number FROM members").map_err(|e| e.to_string())?;
     let members = stmt.query_map([], |row| {
         Ok(Member {
@@ -162,27 +261,27 @@ pub fn get_member_list(app_handle: AppHandle) -> Result<Vec<Member>, String> {
     for member in members {
         result.push(member.map_err(|e| e.to_string())?);
     }
-    
+
     Ok(result)
 }
 
 #[tauri::command]
-pub fn create_member(app_handle: AppHandle, prefect_number: String, role: String, name: Option<String>) -> Result<String, String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
+pub fn create_member(pool: State<'_, DbPool>, prefect_number: String, role: String, name: Option<String>) -> Result<String, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
     let id = Uuid::new_v4().to_string();
-    
+
     conn.execute(
         "INSERT INTO members (id, prefect_number, role, name) VALUES (?1, ?2, ?3, ?4)",
         [&id, &prefect_number, &role, &name.unwrap_or_default()],
     ).map_err(|e| e.to_string())?;
-    
+
     Ok(id)
 }
 
 #[tauri::command]
-pub fn update_member(app_handle: AppHandle, id: String, prefect_number: Option<String>, role: Option<String>, name: Option<String>) -> Result<(), String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
-    
+pub fn update_member(pool: State<'_, DbPool>, id: String, prefect_number: Option<String>, role: Option<String>, name: Option<String>) -> Result<(), String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+
     if let Some(pn) = prefect_number {
         conn.execute("UPDATE members SET prefect_number = ?1 WHERE id = ?2", [&pn, &id]).map_err(|e| e.to_string())?;
     }
@@ -192,40 +291,333 @@ pub fn update_member(app_handle: AppHandle, id: String, prefect_number: Option<S
     if let Some(n) = name {
         conn.execute("UPDATE members SET name = ?1 WHERE id = ?2", [&n, &id]).map_err(|e| e.to_string())?;
     }
-    
+
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_member(app_handle: AppHandle, id: String) -> Result<(), String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
+pub fn delete_member(pool: State<'_, DbPool>, id: String) -> Result<(), String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM members WHERE id = ?1", [&id]).map_err(|e| e.to_string())?;
     Ok(())
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttendanceHistoryEntry {
+    id: i64,
+    record_id: String,
+    old_status: String,
+    old_timestamp: String,
+    changed_by: Option<String>,
+    changed_at: String,
+    action: String,
+}
+
+#[tauri::command]
+pub fn update_attendance_status(pool: State<'_, DbPool>, id: String, status: String, changed_by: Option<String>) -> Result<(), String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let timestamp = chrono::Local::now().to_rfc3339();
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let rows_changed = tx.execute(
+        "UPDATE attendance SET status = ?1, timestamp = ?2 WHERE id = ?3",
+        [&status, &timestamp, &id],
+    ).map_err(|e| e.to_string())?;
+
+    if rows_changed == 0 {
+        return Err(format!("No attendance record found with id {}", id));
+    }
+
+    // The AFTER UPDATE trigger already logged the old status/timestamp; attach
+    // who made the change to the row it just inserted. `last_insert_rowid()`
+    // reverts to its pre-trigger value once the UPDATE statement finishes, so
+    // it can't be used here - target the latest history row for this record
+    // by id instead.
+    tx.execute(
+        "UPDATE attendance_history
+         SET changed_by = ?1
+         WHERE id = (SELECT MAX(id) FROM attendance_history WHERE record_id = ?2)",
+        rusqlite::params![changed_by, id],
+    ).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_attendance(pool: State<'_, DbPool>, id: String, changed_by: Option<String>) -> Result<(), String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let rows_changed = tx.execute("DELETE FROM attendance WHERE id = ?1", [&id]).map_err(|e| e.to_string())?;
+
+    if rows_changed == 0 {
+        return Err(format!("No attendance record found with id {}", id));
+    }
+
+    // Same caveat as update_attendance_status: `last_insert_rowid()` doesn't
+    // survive the DELETE's own trigger firing, so target the history row by
+    // record_id instead.
+    tx.execute(
+        "UPDATE attendance_history
+         SET changed_by = ?1
+         WHERE id = (SELECT MAX(id) FROM attendance_history WHERE record_id = ?2)",
+        rusqlite::params![changed_by, id],
+    ).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_attendance_history(pool: State<'_, DbPool>, record_id: String) -> Result<Vec<AttendanceHistoryEntry>, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, record_id, old_status, old_timestamp, changed_by, changed_at, action
+         FROM attendance_history
+         WHERE record_id = ?1
+         ORDER BY changed_at DESC"
+    ).map_err(|e| e.to_string())?;
+
+    let entries = stmt.query_map([&record_id], |row| {
+        Ok(AttendanceHistoryEntry {
+            id: row.get(0)?,
+            record_id: row.get(1)?,
+            old_status: row.get(2)?,
+            old_timestamp: row.get(3)?,
+            changed_by: row.get(4)?,
+            changed_at: row.get(5)?,
+            action: row.get(6)?,
+        })
+    }).map_err(|e| e.to_string())?;
+
+    let mut result = Vec::new();
+    for entry in entries {
+        result.push(entry.map_err(|e| e.to_string())?);
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_setting(pool: State<'_, DbPool>, key: String) -> Result<Option<String>, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", [&key], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_setting(pool: State<'_, DbPool>, key: String, value: String) -> Result<(), String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [&key, &value],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingEntry {
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupEnvelope {
+    schema_version: i64,
+    members: Vec<Member>,
+    attendance: Vec<AttendanceRecord>,
+    settings: Vec<SettingEntry>,
+}
+
+#[tauri::command]
+pub fn export_backup_json(pool: State<'_, DbPool>) -> Result<String, String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
+
+    let mut members_stmt = conn.prepare("SELECT id, name, role, prefect_number FROM members").map_err(|e| e.to_string())?;
+    let members = members_stmt.query_map([], |row| {
+        Ok(Member {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            role: row.get(2)?,
+            prefect_number: row.get(3)?,
+        })
+    }).map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>().map_err(|e| e.to_string())?;
+
+    let mut attendance_stmt = conn.prepare(
+        "SELECT a.id, a.member_id, a.date, a.timestamp, a.status, m.prefect_number, m.role
+         FROM attendance a
+         JOIN members m ON a.member_id = m.id"
+    ).map_err(|e| e.to_string())?;
+    let attendance = attendance_stmt.query_map([], |row| {
+        Ok(AttendanceRecord {
+            id: row.get(0)?,
+            member_id: row.get(1)?,
+            date: row.get(2)?,
+            timestamp: row.get(3)?,
+            status: row.get(4)?,
+            prefect_number: row.get(5)?,
+            role: row.get(6)?,
+        })
+    }).map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>().map_err(|e| e.to_string())?;
+
+    let mut settings_stmt = conn.prepare("SELECT key, value FROM settings").map_err(|e| e.to_string())?;
+    let settings = settings_stmt.query_map([], |row| {
+        Ok(SettingEntry { key: row.get(0)?, value: row.get(1)? })
+    }).map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>().map_err(|e| e.to_string())?;
+
+    let envelope = BackupEnvelope {
+        schema_version: crate::db::SCHEMA_VERSION,
+        members,
+        attendance,
+        settings,
+    };
+
+    serde_json::to_string(&envelope).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn import_backup_json(pool: State<'_, DbPool>, backup_json: String, mode: String) -> Result<(), String> {
+    let merge = match mode.as_str() {
+        "merge" => true,
+        "replace" => false,
+        other => return Err(format!("Unknown import mode '{}': expected 'merge' or 'replace'", other)),
+    };
+
+    let envelope: BackupEnvelope = serde_json::from_str(&backup_json).map_err(|e| e.to_string())?;
+
+    if envelope.schema_version > crate::db::SCHEMA_VERSION {
+        return Err(format!(
+            "Backup was created with a newer schema (v{}) than this app supports (v{})",
+            envelope.schema_version,
+            crate::db::SCHEMA_VERSION
+        ));
+    }
+
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    if !merge {
+        tx.execute("DELETE FROM attendance", []).map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM members", []).map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM settings", []).map_err(|e| e.to_string())?;
+        // The AFTER DELETE trigger logs every row the attendance delete above
+        // removed; a replace-mode restore should clear that history too
+        // instead of leaving a pile of phantom "deleted" entries behind.
+        tx.execute("DELETE FROM attendance_history", []).map_err(|e| e.to_string())?;
+    }
+
+    // Upserting members by prefect_number can keep an existing local id, so
+    // remap backup member ids to whichever id actually ended up in the table
+    // before inserting attendance rows that reference them.
+    let mut member_id_map: HashMap<String, String> = HashMap::new();
+
+    for member in &envelope.members {
+        let local_id = if merge {
+            let existing: Option<String> = tx.query_row(
+                "SELECT id FROM members WHERE prefect_number = ?1",
+                [&member.prefect_number],
+                |row| row.get(0),
+            ).optional().map_err(|e| e.to_string())?;
+
+            match existing {
+                Some(existing_id) => {
+                    tx.execute(
+                        "UPDATE members SET name = ?1, role = ?2 WHERE id = ?3",
+                        rusqlite::params![member.name, member.role, existing_id],
+                    ).map_err(|e| e.to_string())?;
+                    existing_id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO members (id, name, role, prefect_number) VALUES (?1, ?2, ?3, ?4)",
+                        rusqlite::params![member.id, member.name, member.role, member.prefect_number],
+                    ).map_err(|e| e.to_string())?;
+                    member.id.clone()
+                }
+            }
+        } else {
+            tx.execute(
+                "INSERT INTO members (id, name, role, prefect_number) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![member.id, member.name, member.role, member.prefect_number],
+            ).map_err(|e| e.to_string())?;
+            member.id.clone()
+        };
+
+        member_id_map.insert(member.id.clone(), local_id);
+    }
+
+    for record in &envelope.attendance {
+        let Some(local_member_id) = member_id_map.get(&record.member_id) else {
+            continue;
+        };
+
+        if merge {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM attendance WHERE member_id = ?1 AND date = ?2)",
+                rusqlite::params![local_member_id, record.date],
+                |row| row.get(0),
+            ).map_err(|e| e.to_string())?;
+            if exists {
+                continue;
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO attendance (id, member_id, date, timestamp, status) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![record.id, local_member_id, record.date, record.timestamp, record.status],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    for setting in &envelope.settings {
+        tx.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![setting.key, setting.value],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn export_backup(app_handle: AppHandle) -> Result<String, String> {
     // Basic implementation: dump DB to JSON or copy file
     // For now, let's just return the path to the DB file so frontend can download it?
     // Or better, read the file and return base64.
-    let db_path = db::get_db_path(&app_handle).map_err(|e| e.to_string())?;
+    let db_path = crate::db::get_db_path(&app_handle).map_err(|e| e.to_string())?;
     let content = std::fs::read(db_path).map_err(|e| e.to_string())?;
     Ok(base64::encode(content))
 }
 
 #[tauri::command]
 pub fn import_backup(app_handle: AppHandle, backup_data: String) -> Result<(), String> {
-    let db_path = db::get_db_path(&app_handle).map_err(|e| e.to_string())?;
+    let db_path = crate::db::get_db_path(&app_handle).map_err(|e| e.to_string())?;
     let content = base64::decode(backup_data).map_err(|e| e.to_string())?;
     std::fs::write(db_path, content).map_err(|e| e.to_string())?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn wipe_all_data(app_handle: AppHandle) -> Result<(), String> {
-    let conn = db::get_connection(&app_handle).map_err(|e| e.to_string())?;
+pub fn wipe_all_data(pool: State<'_, DbPool>) -> Result<(), String> {
+    let conn = pool.get().map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM attendance", []).map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM members", []).map_err(|e| e.to_string())?;
+    // The AFTER DELETE trigger logs every row the attendance delete above
+    // removed; a full wipe should clear that history too instead of leaving
+    // a pile of phantom "deleted" entries behind.
+    conn.execute("DELETE FROM attendance_history", []).map_err(|e| e.to_string())?;
     Ok(())
 }
 
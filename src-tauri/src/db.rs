@@ -1,72 +1,275 @@
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{Connection, Result};
 use std::path::PathBuf;
 use tauri::AppHandle;
 use std::fs;
 
+/// Pooled connections, managed as Tauri state so commands don't each open a
+/// fresh file handle to `attendance.db`.
+pub type DbPool = Pool<SqliteConnectionManager>;
+
 pub fn get_db_path(app_handle: &AppHandle) -> Result<PathBuf> {
     let app_dir = app_handle.path_resolver().app_data_dir().ok_or_else(|| {
         rusqlite::Error::InvalidPath(PathBuf::from("App data dir not found"))
     })?;
-    
+
     if !app_dir.exists() {
         fs::create_dir_all(&app_dir).map_err(|_| {
             rusqlite::Error::InvalidPath(app_dir.clone())
         })?;
     }
-    
+
     Ok(app_dir.join("attendance.db"))
 }
 
+/// Ordered schema migrations, keyed off `PRAGMA user_version`.
+///
+/// Each entry is applied exactly once: index `i` in this slice is migration
+/// version `i + 1`. Add new migrations by appending to the end - never edit
+/// or reorder an existing entry, since that would change what already-applied
+/// databases think they've run.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "CREATE TABLE IF NOT EXISTS members (
+        id TEXT PRIMARY KEY,
+        name TEXT,
+        role TEXT NOT NULL,
+        prefect_number TEXT UNIQUE NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS attendance (
+        id TEXT PRIMARY KEY,
+        member_id TEXT NOT NULL,
+        date TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        status TEXT NOT NULL,
+        FOREIGN KEY(member_id) REFERENCES members(id)
+    );
+    CREATE TABLE IF NOT EXISTS backups (
+        id TEXT PRIMARY KEY,
+        created_at TEXT NOT NULL,
+        path TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );",
+    // 2: cascade deletes from members into attendance, plus lookup indexes.
+    // SQLite can't ALTER a foreign key in place, so the table is rebuilt.
+    // `run_migrations` toggles `PRAGMA foreign_keys` around this migration's
+    // transaction, since the pragma is a no-op once a transaction is open.
+    "CREATE TABLE attendance_new (
+        id TEXT PRIMARY KEY,
+        member_id TEXT NOT NULL,
+        date TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        status TEXT NOT NULL,
+        FOREIGN KEY(member_id) REFERENCES members(id) ON DELETE CASCADE
+    );
+
+    INSERT INTO attendance_new (id, member_id, date, timestamp, status)
+        SELECT id, member_id, date, timestamp, status FROM attendance;
+
+    DROP TABLE attendance;
+    ALTER TABLE attendance_new RENAME TO attendance;
+
+    CREATE INDEX IF NOT EXISTS idx_attendance_date ON attendance(date);
+    CREATE INDEX IF NOT EXISTS idx_attendance_member_id ON attendance(member_id);",
+    // 3: audit trail for manual corrections. The triggers capture the old
+    // value straight from the row being changed so application code can't
+    // forget to log it; `changed_by` is filled in afterwards by the command
+    // that made the change, on the latest history row for that record.
+    "CREATE TABLE IF NOT EXISTS attendance_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        record_id TEXT NOT NULL,
+        old_status TEXT NOT NULL,
+        old_timestamp TEXT NOT NULL,
+        changed_by TEXT,
+        changed_at TEXT NOT NULL,
+        action TEXT NOT NULL
+    );
+
+    CREATE TRIGGER IF NOT EXISTS trg_attendance_after_update
+    AFTER UPDATE ON attendance
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO attendance_history (record_id, old_status, old_timestamp, changed_at, action)
+        VALUES (OLD.id, OLD.status, OLD.timestamp, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), 'edit');
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS trg_attendance_after_delete
+    AFTER DELETE ON attendance
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO attendance_history (record_id, old_status, old_timestamp, changed_at, action)
+        VALUES (OLD.id, OLD.status, OLD.timestamp, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), 'delete');
+    END;",
+];
+
+/// The current schema version, i.e. the `user_version` a freshly migrated
+/// database ends up at. Backups embed this so imports can refuse anything
+/// newer than what this build of the app understands.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// Applies every migration in `MIGRATIONS` with an index greater than the
+/// database's current `user_version`, each inside its own transaction so a
+/// failure partway through rolls back instead of leaving the schema half
+/// upgraded. `user_version` is advanced to the new high-water mark as each
+/// migration commits.
+///
+/// `PRAGMA foreign_keys` is toggled off before and back on after each
+/// migration's transaction - never inside one, where the pragma is a no-op -
+/// so a migration that rebuilds a table to change a foreign key doesn't trip
+/// FK checks on its own `DROP TABLE`/rename.
+fn run_migrations(conn: &mut Connection) -> Result<()> {
+    let current_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (i, migration) in MIGRATIONS.iter().enumerate() {
+        let version = (i + 1) as i64;
+        if version <= current_version {
+            continue;
+        }
+
+        conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.execute_batch(&format!("PRAGMA user_version = {}", version))?;
+        tx.commit()?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    }
+
+    Ok(())
+}
+
 pub fn init_db(app_handle: &AppHandle) -> Result<()> {
     let db_path = get_db_path(app_handle)?;
-    let conn = Connection::open(db_path)?;
+    let mut conn = Connection::open(db_path)?;
 
     // Enable WAL mode for better concurrency
-    conn.execute_batch("PRAGMA journal_mode = WAL;")?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS members (
-            id TEXT PRIMARY KEY,
-            name TEXT,
-            role TEXT NOT NULL,
-            prefect_number TEXT UNIQUE NOT NULL
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS attendance (
-            id TEXT PRIMARY KEY,
-            member_id TEXT NOT NULL,
-            date TEXT NOT NULL,
-            timestamp TEXT NOT NULL,
-            status TEXT NOT NULL,
-            FOREIGN KEY(member_id) REFERENCES members(id)
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS backups (
-            id TEXT PRIMARY KEY,
-            created_at TEXT NOT NULL,
-            path TEXT NOT NULL
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS settings (
-            key TEXT PRIMARY KEY,
-            value TEXT NOT NULL
-        )",
-        [],
-    )?;
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")?;
 
-    Ok(())
+    run_migrations(&mut conn)
 }
 
 pub fn get_connection(app_handle: &AppHandle) -> Result<Connection> {
     let db_path = get_db_path(app_handle)?;
     Connection::open(db_path)
 }
+
+/// Builds the pool used for the lifetime of the app. Every connection the
+/// pool opens runs through `with_init`, so WAL mode and foreign key
+/// enforcement are applied consistently no matter which pooled connection a
+/// command ends up with.
+pub fn create_pool(app_handle: &AppHandle) -> Result<DbPool> {
+    let db_path = get_db_path(app_handle)?;
+    let manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+    });
+
+    Pool::new(manager).map_err(|e| rusqlite::Error::InvalidPath(PathBuf::from(e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrated_conn() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn deleting_a_member_cascades_to_their_attendance() {
+        let conn = migrated_conn();
+
+        conn.execute(
+            "INSERT INTO members (id, role, prefect_number) VALUES ('m1', 'prefect', 'P001')",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO attendance (id, member_id, date, timestamp, status)
+             VALUES ('a1', 'm1', '2026-07-28', '2026-07-28T07:00:00+00:00', 'Present')",
+            [],
+        ).unwrap();
+
+        conn.execute("DELETE FROM members WHERE id = 'm1'", []).unwrap();
+
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM attendance WHERE member_id = 'm1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 0, "attendance rows should be cascade-deleted with their member");
+    }
+
+    #[test]
+    fn editing_attendance_logs_the_old_value_to_history() {
+        let conn = migrated_conn();
+
+        conn.execute(
+            "INSERT INTO members (id, role, prefect_number) VALUES ('m1', 'prefect', 'P001')",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO attendance (id, member_id, date, timestamp, status)
+             VALUES ('a1', 'm1', '2026-07-28', '2026-07-28T07:00:00+00:00', 'Late')",
+            [],
+        ).unwrap();
+
+        conn.execute(
+            "UPDATE attendance SET status = 'Present' WHERE id = 'a1'",
+            [],
+        ).unwrap();
+
+        let (old_status, action): (String, String) = conn.query_row(
+            "SELECT old_status, action FROM attendance_history WHERE record_id = 'a1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap();
+
+        assert_eq!(old_status, "Late");
+        assert_eq!(action, "edit");
+    }
+
+    #[test]
+    fn editing_attendance_attributes_the_right_history_row_to_the_editor() {
+        let mut conn = migrated_conn();
+
+        conn.execute(
+            "INSERT INTO members (id, role, prefect_number) VALUES ('m1', 'prefect', 'P001')",
+            [],
+        ).unwrap();
+        // A prior insert on this connection so last_insert_rowid() has
+        // already moved past the history row we're about to create - this is
+        // what exposed the attribution bug commands.rs used to have.
+        conn.execute(
+            "INSERT INTO attendance (id, member_id, date, timestamp, status)
+             VALUES ('a0', 'm1', '2026-07-27', '2026-07-27T07:00:00+00:00', 'Present')",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO attendance (id, member_id, date, timestamp, status)
+             VALUES ('a1', 'm1', '2026-07-28', '2026-07-28T07:00:00+00:00', 'Late')",
+            [],
+        ).unwrap();
+
+        // Mirrors update_attendance_status's two-statement sequence.
+        let tx = conn.transaction().unwrap();
+        tx.execute("UPDATE attendance SET status = 'Present' WHERE id = 'a1'", []).unwrap();
+        tx.execute(
+            "UPDATE attendance_history
+             SET changed_by = ?1
+             WHERE id = (SELECT MAX(id) FROM attendance_history WHERE record_id = ?2)",
+            rusqlite::params!["admin@example.com", "a1"],
+        ).unwrap();
+        tx.commit().unwrap();
+
+        let changed_by: Option<String> = conn.query_row(
+            "SELECT changed_by FROM attendance_history WHERE record_id = 'a1'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+
+        assert_eq!(changed_by.as_deref(), Some("admin@example.com"));
+    }
+}
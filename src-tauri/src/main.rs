@@ -7,12 +7,6 @@ mod db;
 use commands::*;
 
 fn main() {
-    // Initialize database
-    if let Err(e) = db::init_db() {
-        eprintln!("Failed to initialize database: {}", e);
-        std::process::exit(1);
-    }
-
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
@@ -29,9 +23,30 @@ fn main() {
             wipe_all_data,
             get_app_version,
             check_for_updates,
-            get_all_attendance
+            get_all_attendance,
+            update_attendance_status,
+            delete_attendance,
+            get_attendance_history,
+            get_setting,
+            set_setting,
+            export_backup_json,
+            import_backup_json,
+            query_attendance
         ])
-        .setup(|_app| {
+        .setup(|app| {
+            let app_handle = app.handle();
+
+            if let Err(e) = db::init_db(&app_handle) {
+                eprintln!("Failed to initialize database: {}", e);
+                std::process::exit(1);
+            }
+
+            let pool = db::create_pool(&app_handle).unwrap_or_else(|e| {
+                eprintln!("Failed to create database connection pool: {}", e);
+                std::process::exit(1);
+            });
+            app.manage(pool);
+
             Ok(())
         })
         .run(tauri::generate_context!())